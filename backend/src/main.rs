@@ -3,18 +3,109 @@
     windows_subsystem = "windows"
 )]
 
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tauri::Manager;
 use tauri_glue::*;
+use thiserror::Error;
+
+#[derive(Clone, Serialize)]
+struct RowCreated {
+    iid: usize,
+    rid: usize,
+    label: String,
+}
+
+#[tauri_glue::emit(name = row_created)]
+fn emit_row_created(app: &tauri::AppHandle, payload: RowCreated);
+
+// Serialized across the Tauri error channel and reconstructed by the
+// frontend stub instead of collapsing to a stringified message.
+#[derive(Debug, Serialize, Deserialize, Error)]
+enum CreateRowError {
+    #[error("label must not be empty")]
+    EmptyLabel,
+    #[error("label too long: {len} bytes")]
+    LabelTooLong { len: usize },
+}
+
+#[tauri_glue::command(batch)]
+fn create_row(
+    app: tauri::AppHandle,
+    iid: usize,
+    rid: usize,
+    label: String,
+) -> Result<(), CreateRowError> {
+    if label.is_empty() {
+        return Err(CreateRowError::EmptyLabel);
+    }
+    if label.len() > 64 {
+        return Err(CreateRowError::LabelTooLong { len: label.len() });
+    }
 
-#[tauri_glue::command]
-fn create_row(iid: usize, rid: usize, label: String) -> Result<(), ()> {
     // println!("Creating {iid}th row with rid: {rid} and label: {label}!");
+    emit_row_created(&app, RowCreated { iid, rid, label });
+    Ok(())
+}
+
+// One Tauri command is registered per instantiation listed in `types`:
+// `upsert_usize` and `upsert_String`.
+#[tauri_glue::command(types(usize, String))]
+fn upsert<T: Serialize + DeserializeOwned>(id: usize, value: T) -> Result<(), ()> {
+    let _ = (id, value);
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `#[tauri_glue::command(types(usize, String))]` must register one
+    /// invoke handler per listed type; these coerce each generated wrapper
+    /// to a distinct, concrete function pointer type, so the test fails to
+    /// compile if either wrapper is missing or collapses onto the other.
+    #[test]
+    fn upsert_registers_one_invoke_handler_per_type() {
+        let _usize_handler: fn(usize, usize) -> Result<(), ()> = upsert_usize;
+        let _string_handler: fn(usize, String) -> Result<(), ()> = upsert_String;
+    }
+
+    // The frontend's `bind_command` shim never runs `serde_wasm_bindgen`
+    // natively, so this stands in for the actual boundary: it mirrors the
+    // frontend's hand-written `CreateRowError` copy and round-trips through
+    // `serde_json`, the same tagging `serde_wasm_bindgen` relies on, to show
+    // a backend-side variant with fields survives the crossing and
+    // pattern-matches on the other side.
+    #[derive(Debug, Deserialize)]
+    enum FrontendCreateRowError {
+        EmptyLabel,
+        LabelTooLong { len: usize },
+    }
+
+    #[test]
+    fn create_row_error_round_trips_to_frontend_mirror() {
+        let backend_err = CreateRowError::LabelTooLong { len: 65 };
+
+        let wire = serde_json::to_value(&backend_err).expect("serialize backend error");
+        let frontend_err: FrontendCreateRowError =
+            serde_json::from_value(wire).expect("deserialize into frontend mirror");
+
+        match frontend_err {
+            FrontendCreateRowError::LabelTooLong { len } => assert_eq!(len, 65),
+            FrontendCreateRowError::EmptyLabel => panic!("expected LabelTooLong"),
+        }
+    }
+}
+
 fn main() {
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![create_row])
+        // `batch` registers an extra `create_row_batch` handler alongside the
+        // scalar one, so bulk inserts can ride a single invoke round-trip.
+        .invoke_handler(tauri::generate_handler![
+            create_row,
+            create_row_batch,
+            upsert_usize,
+            upsert_String
+        ])
         .setup(|app| {
             #[cfg(debug_assertions)] // only include this code on debug builds
             {
@@ -3,7 +3,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::web_sys::MouseEvent;
 use leptos::*;
 use rand::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tauri_glue::*;
+use thiserror::Error;
 
 static ADJECTIVES: &[&str] = &[
     "pretty",
@@ -51,8 +53,46 @@ struct RowData {
 
 static ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
 
-#[tauri_glue::bind_command(name = create_row)]
-pub async fn create_row(iid: usize, rid: usize, label: String) -> Result<(), ()>;
+// Mirrors the backend's `CreateRowError`; the bind_command shim
+// deserializes the Tauri error channel's payload into this enum instead of
+// a stringified message.
+#[derive(Debug, Clone, Deserialize, Serialize, Error)]
+enum CreateRowError {
+    #[error("label must not be empty")]
+    EmptyLabel,
+    #[error("label too long: {len} bytes")]
+    LabelTooLong { len: usize },
+}
+
+// `trace` times serialization, the invoke round-trip, and deserialization
+// separately and folds them into `tauri_glue::metrics`'s per-command
+// histogram, so IPC cost can be read apart from rendering cost.
+#[tauri_glue::bind_command(name = create_row, batch, trace)]
+pub async fn create_row(iid: usize, rid: usize, label: String) -> Result<(), CreateRowError>;
+
+fn create_row_ipc_mean() -> f64 {
+    tauri_glue::metrics::snapshot()
+        .get("create_row")
+        .map(|stats| stats.mean)
+        .unwrap_or(0.0)
+}
+
+// The generic wrapper dispatches to `upsert_usize` or `upsert_String` based
+// on the monomorphization of `T` at the call site.
+#[tauri_glue::bind_command(name = upsert, types(usize, String))]
+pub async fn upsert<T: Serialize + DeserializeOwned>(id: usize, value: T) -> Result<(), ()>;
+
+#[derive(Clone, Deserialize)]
+struct RowCreated {
+    iid: usize,
+    rid: usize,
+    label: String,
+}
+
+// Subscribes to the backend's `row_created` event on first call; each
+// emitted payload is deserialized and pushed through the paired write side.
+#[tauri_glue::listen(name = row_created)]
+pub fn row_created(cx: Scope) -> ReadSignal<Option<RowCreated>>;
 
 async fn build_data(cx: Scope, count: usize) -> Vec<RowData> {
     let mut thread_rng = thread_rng();
@@ -77,16 +117,34 @@ async fn build_data(cx: Scope, count: usize) -> Vec<RowData> {
             label: create_signal(cx, label),
         };
 
-        create_row(i, r_data.id, r_data.label.0())
-            .await
-            .expect("oops");
+        // `batch` buffers this call on a thread-local queue instead of
+        // firing an invoke per row; nothing crosses the IPC boundary until
+        // `create_row_flush` is awaited below.
+        create_row(i, r_data.id, r_data.label.0());
 
         data.push(r_data);
 
         ID_COUNTER.store(ID_COUNTER.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
     }
 
-    data
+    // `create_row_flush`'s results align by index with the rows pushed into
+    // `data` above, so a rejected row needs to come back out here too --
+    // otherwise the UI would show a row the backend never persisted.
+    let results = create_row_flush().await;
+    let mut kept = Vec::with_capacity(data.len());
+    for (r_data, result) in data.into_iter().zip(results) {
+        match result {
+            Ok(()) => kept.push(r_data),
+            Err(CreateRowError::EmptyLabel) => {
+                web_sys::console::warn_1(&"skipped row with an empty label".into());
+            }
+            Err(CreateRowError::LabelTooLong { len }) => {
+                web_sys::console::warn_1(&format!("skipped row with a {len}-byte label").into());
+            }
+        }
+    }
+
+    kept
 }
 
 #[component]
@@ -104,6 +162,27 @@ fn App(cx: Scope) -> Element {
     let (data, set_data) = create_signal(cx, Vec::<RowData>::new());
     let (selected, set_selected) = create_signal(cx, None::<usize>);
     let (op_time_avg, set_op_time_avg) = create_signal(cx, 0.0);
+    let (ipc_time_avg, set_ipc_time_avg) = create_signal(cx, 0.0);
+
+    // Stream server-pushed rows into the table as they're produced, rather
+    // than only ever reacting to the response of a command we called.
+    let last_created = row_created(cx);
+    create_effect(cx, move |_| {
+        if let Some(event) = last_created() {
+            set_data.update(|data| {
+                // `create_row` emits `row_created` for every row it
+                // persists, including the ones `build_data` already pushed
+                // into `data` from its own return value -- skip ids we've
+                // already got so the two paths don't double-count.
+                if !data.iter().any(|row| row.id == event.rid) {
+                    data.push(RowData {
+                        id: event.rid,
+                        label: create_signal(cx, event.label.clone()),
+                    });
+                }
+            });
+        }
+    });
 
     let remove = move |id| {
         set_data.update(move |data| data.retain(|row| row.id != id));
@@ -114,6 +193,7 @@ fn App(cx: Scope) -> Element {
             let start = js_sys::Date::now();
             set_data(build_data(cx, 1000).await);
             set_op_time_avg(js_sys::Date::now() - start);
+            set_ipc_time_avg(create_row_ipc_mean());
         });
         set_selected(None);
     };
@@ -123,6 +203,7 @@ fn App(cx: Scope) -> Element {
             let start = js_sys::Date::now();
             set_data(build_data(cx, 10000).await);
             set_op_time_avg((js_sys::Date::now() - start) / 10.0);
+            set_ipc_time_avg(create_row_ipc_mean());
         });
         set_selected(None);
     };
@@ -130,18 +211,31 @@ fn App(cx: Scope) -> Element {
     let add = move |_| {
         spawn_local(async move {
             let start = js_sys::Date::now();
-            let mut rows = build_data(cx, 1000).await;
+            let rows = build_data(cx, 1000).await;
             set_data.update(move |data| {
+                // `row_created` fires for every row while `build_data` is
+                // still awaiting, racing its own append below -- keep only
+                // the ids the listener hasn't already pushed in.
+                let mut rows = rows;
+                rows.retain(|row| !data.iter().any(|existing| existing.id == row.id));
                 data.append(&mut rows);
             });
             set_op_time_avg(js_sys::Date::now() - start);
+            set_ipc_time_avg(create_row_ipc_mean());
         });
     };
 
     let update = move |_| {
+        let mut persisted = Vec::new();
         data.with(|data| {
             for row in data.iter().step_by(10) {
                 row.label.1.update(|n| n.push_str(" !!!"));
+                persisted.push((row.id, row.label.0()));
+            }
+        });
+        spawn_local(async move {
+            for (id, label) in persisted {
+                upsert(id, label).await.expect("oops");
             }
         });
     };
@@ -150,6 +244,8 @@ fn App(cx: Scope) -> Element {
         set_data(Vec::new());
         set_selected(None);
         set_op_time_avg(0.0);
+        set_ipc_time_avg(0.0);
+        tauri_glue::metrics::reset();
     };
 
     let swap_rows = move |_| {
@@ -166,7 +262,7 @@ fn App(cx: Scope) -> Element {
         cx,
         <div class="container">
             <div class="jumbotron"><div class="row">
-            <div class="col-md-6"><h1>"Leptos"</h1><h2>"Avg. Operation Time: "{op_time_avg}"µs"</h2></div>
+            <div class="col-md-6"><h1>"Leptos"</h1><h2>"Avg. Operation Time: "{op_time_avg}"µs"</h2><h2>"Avg. IPC Time: "{ipc_time_avg}"ms"</h2></div>
             <div class="col-md-6"><div class="row">
                 <Button id="run".to_string() text="Create 1,000 rows".to_string() on:click=run />
                 <Button id="runlots".to_string() text="Create 10,000 rows".to_string() on:click=run_lots />
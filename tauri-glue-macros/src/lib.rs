@@ -0,0 +1,570 @@
+//! Proc-macro codegen for `tauri_glue`. Each attribute here expands a thin,
+//! declarative annotation into the boilerplate a hand-written Tauri command
+//! or frontend stub would otherwise need.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, FnArg, ForeignItemFn, GenericArgument, ItemFn, Pat, PathArguments,
+    ReturnType, Token, Type,
+};
+
+/// Arguments accepted by `#[tauri_glue::command]` / `#[tauri_glue::bind_command]`:
+/// `name = ident`, the bare `batch` and `trace` flags, and `types(T0, T1, ..)`.
+struct MacroArgs {
+    name: Option<syn::Ident>,
+    batch: bool,
+    trace: bool,
+    types: Vec<Type>,
+}
+
+impl Parse for MacroArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = MacroArgs {
+            name: None,
+            batch: false,
+            trace: false,
+            types: Vec::new(),
+        };
+        let metas = Punctuated::<syn::Meta, Token![,]>::parse_terminated(input)?;
+        for meta in metas {
+            match meta {
+                syn::Meta::Path(p) if p.is_ident("batch") => args.batch = true,
+                syn::Meta::Path(p) if p.is_ident("trace") => args.trace = true,
+                syn::Meta::NameValue(nv) if nv.path.is_ident("name") => {
+                    let syn::Expr::Path(p) = &nv.value else {
+                        return Err(syn::Error::new_spanned(&nv.value, "expected `name = ident`"));
+                    };
+                    args.name = p.path.get_ident().cloned();
+                }
+                syn::Meta::List(list) if list.path.is_ident("types") => {
+                    let parsed: Punctuated<Type, Token![,]> =
+                        list.parse_args_with(Punctuated::parse_terminated)?;
+                    args.types.extend(parsed);
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "unsupported tauri_glue argument",
+                    ))
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// The identifier a monomorphization's Tauri command name is suffixed with,
+/// e.g. `usize` in `upsert_usize`.
+fn type_suffix(ty: &Type) -> syn::Ident {
+    let Type::Path(p) = ty else {
+        panic!("#[tauri_glue::command(types(..))] only supports path types")
+    };
+    let last = &p.path.segments.last().expect("non-empty type path").ident;
+    format_ident!("{}", last)
+}
+
+/// Parameters of a command/bind_command signature split into the ones that
+/// are injected by Tauri itself (`tauri::AppHandle`, `tauri::Window`,
+/// `tauri::State<..>`) and the ones that actually cross the IPC boundary as
+/// serialized arguments.
+struct SplitParams {
+    injected: Vec<FnArg>,
+    data: Vec<(syn::Ident, Type)>,
+}
+
+fn is_injected_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => is_injected_type(&r.elem),
+        Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident == "AppHandle" || seg.ident == "Window" || seg.ident == "State")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn split_params(inputs: &Punctuated<FnArg, Token![,]>) -> SplitParams {
+    let mut injected = Vec::new();
+    let mut data = Vec::new();
+    for arg in inputs {
+        let FnArg::Typed(pat_ty) = arg else {
+            continue;
+        };
+        if is_injected_type(&pat_ty.ty) {
+            injected.push(arg.clone());
+        } else if let Pat::Ident(id) = &*pat_ty.pat {
+            data.push((id.ident.clone(), (*pat_ty.ty).clone()));
+        }
+    }
+    SplitParams { injected, data }
+}
+
+fn injected_idents(split: &SplitParams) -> Vec<syn::Ident> {
+    split
+        .injected
+        .iter()
+        .filter_map(|a| match a {
+            FnArg::Typed(t) => match &*t.pat {
+                Pat::Ident(id) => Some(id.ident.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn result_ok_err(output: &ReturnType) -> Option<(Type, Type)> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(p) = ty.as_ref() else {
+        return None;
+    };
+    let seg = p.path.segments.last()?;
+    if seg.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    let mut iter = args.args.iter();
+    let (Some(GenericArgument::Type(ok)), Some(GenericArgument::Type(err))) =
+        (iter.next(), iter.next())
+    else {
+        return None;
+    };
+    Some((ok.clone(), err.clone()))
+}
+
+/// `#[tauri_glue::command]` / `#[tauri_glue::command(batch)]` / `#[tauri_glue::command(types(..))]`
+///
+/// Backend side. Wraps the annotated function as a real `#[tauri::command]`
+/// and, depending on the arguments, generates the siblings needed to
+/// register additional invoke handlers alongside it.
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
+    let func = parse_macro_input!(item as ItemFn);
+
+    if !args.types.is_empty() {
+        return command_types(args, func);
+    }
+    if args.batch {
+        return command_batch(func);
+    }
+
+    quote! {
+        #[tauri::command]
+        #func
+    }
+    .into()
+}
+
+/// Generates one monomorphized, non-generic `#[tauri::command]` wrapper per
+/// type listed in `types(..)` -- Tauri's own command registration can't
+/// handle a generic function, so the macro has to do the monomorphizing
+/// itself. The last data parameter of the annotated function is assumed to
+/// be the generic one.
+fn command_types(args: MacroArgs, func: ItemFn) -> TokenStream {
+    let vis = &func.vis;
+    let name = &func.sig.ident;
+    let split = split_params(&func.sig.inputs);
+    let (ok, err) = result_ok_err(&func.sig.output)
+        .expect("#[tauri_glue::command(types(..))] requires a `Result<T, E>` return type");
+
+    let injected_params = &split.injected;
+    let injected = injected_idents(&split);
+    let (generic, concrete) = split
+        .data
+        .split_last()
+        .expect("#[tauri_glue::command(types(..))] needs a generic data parameter");
+    let generic_ident = &generic.0;
+    let concrete_idents: Vec<_> = concrete.iter().map(|(id, _)| id.clone()).collect();
+    let concrete_types: Vec<_> = concrete.iter().map(|(_, ty)| ty.clone()).collect();
+
+    let wrappers = args.types.iter().map(|ty| {
+        let suffix = type_suffix(ty);
+        let wrapper_name = format_ident!("{}_{}", name, suffix);
+        quote! {
+            // The wrapper name is derived from the instantiated type so it
+            // stays unique per monomorphization, hence the snake_case
+            // opt-out below.
+            #[allow(non_snake_case)]
+            #[tauri::command]
+            #vis fn #wrapper_name(
+                #(#injected_params,)*
+                #(#concrete_idents: #concrete_types,)*
+                #generic_ident: #ty,
+            ) -> Result<#ok, #err> {
+                #name(#(#injected,)* #(#concrete_idents,)* #generic_ident)
+            }
+        }
+    });
+
+    quote! {
+        #func
+
+        #(#wrappers)*
+    }
+    .into()
+}
+
+fn command_batch(func: ItemFn) -> TokenStream {
+    let vis = &func.vis;
+    let name = &func.sig.ident;
+    let batch_name = format_ident!("{}_batch", name);
+    let split = split_params(&func.sig.inputs);
+    let (ok, err) = result_ok_err(&func.sig.output)
+        .expect("#[tauri_glue::command(batch)] requires a `Result<T, E>` return type");
+
+    let injected_params = &split.injected;
+    let injected = injected_idents(&split);
+    let data_idents: Vec<_> = split.data.iter().map(|(id, _)| id.clone()).collect();
+    let data_types: Vec<_> = split.data.iter().map(|(_, ty)| ty.clone()).collect();
+
+    quote! {
+        #[tauri::command]
+        #func
+
+        /// Registered alongside the scalar command: loops over a `Vec` of
+        /// argument tuples buffered by the frontend's batching stub and
+        /// returns one `Result` per input, preserving call order.
+        #[tauri::command]
+        #vis fn #batch_name(
+            #(#injected_params,)*
+            args: Vec<(#(#data_types,)*)>,
+        ) -> Vec<Result<#ok, #err>> {
+            args
+                .into_iter()
+                .map(|(#(#data_idents,)*)| #name(#(#injected.clone(),)* #(#data_idents,)*))
+                .collect()
+        }
+    }
+    .into()
+}
+
+fn command_name(args: &MacroArgs, fallback: &syn::Ident) -> String {
+    args.name
+        .as_ref()
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// `#[tauri_glue::bind_command(name = .., batch, types(..))]`
+///
+/// Frontend side. Turns a signature-only declaration into a real `invoke`
+/// call, a batching push/flush pair, or a generic dispatcher over the
+/// enumerated `types(..)`.
+#[proc_macro_attribute]
+pub fn bind_command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
+    let decl = parse_macro_input!(item as ForeignItemFn);
+
+    if !args.types.is_empty() {
+        return bind_command_types(args, decl);
+    }
+    if args.batch {
+        return bind_command_batch(args, decl);
+    }
+    bind_command_plain(args, decl)
+}
+
+fn bind_command_plain(args: MacroArgs, decl: ForeignItemFn) -> TokenStream {
+    let vis = &decl.vis;
+    let sig = &decl.sig;
+    let fn_name = &sig.ident;
+    let cmd = command_name(&args, fn_name);
+    let split = split_params(&sig.inputs);
+    let (ok, err) = result_ok_err(&sig.output)
+        .expect("#[tauri_glue::bind_command] requires a `Result<T, E>` return type");
+    let args_struct = format_ident!("__{}Args", fn_name);
+    let data_idents: Vec<_> = split.data.iter().map(|(id, _)| id.clone()).collect();
+    let data_types: Vec<_> = split.data.iter().map(|(_, ty)| ty.clone()).collect();
+
+    let call = if args.trace {
+        quote! {
+            let __t0 = js_sys::Date::now();
+            let __js_args = tauri_glue::rt::to_js_value(&#args_struct { #(#data_idents),* });
+            let __t1 = js_sys::Date::now();
+            let __js_result = tauri_glue::rt::invoke_raw(#cmd, __js_args).await;
+            let __t2 = js_sys::Date::now();
+            let __result = match __js_result {
+                Ok(__ok) => Ok(tauri_glue::rt::from_js_value(__ok)),
+                Err(__rejected) => Err(tauri_glue::rt::from_js_error(__rejected)),
+            };
+            let __t3 = js_sys::Date::now();
+            tauri_glue::metrics::record(#cmd, __t1 - __t0, __t2 - __t1, __t3 - __t2);
+            __result
+        }
+    } else {
+        quote! {
+            tauri_glue::rt::invoke(#cmd, &#args_struct { #(#data_idents),* }).await
+        }
+    };
+
+    quote! {
+        #vis async fn #fn_name(#(#data_idents: #data_types),*) -> Result<#ok, #err> {
+            // The struct name is derived from the command's snake_case
+            // identifier, so it can't itself be upper camel case.
+            #[allow(non_camel_case_types)]
+            #[derive(serde::Serialize)]
+            struct #args_struct {
+                #(#data_idents: #data_types),*
+            }
+            #call
+        }
+    }
+    .into()
+}
+
+fn bind_command_batch(args: MacroArgs, decl: ForeignItemFn) -> TokenStream {
+    let vis = &decl.vis;
+    let sig = &decl.sig;
+    let fn_name = &sig.ident;
+    let cmd = command_name(&args, fn_name);
+    let batch_cmd = format!("{cmd}_batch");
+    let flush_name = format_ident!("{}_flush", fn_name);
+    let queue_name = format_ident!("__{}_QUEUE", fn_name.to_string().to_uppercase());
+    let split = split_params(&sig.inputs);
+    let (ok, err) = result_ok_err(&sig.output)
+        .expect("#[tauri_glue::bind_command(batch)] requires a `Result<T, E>` return type");
+    let data_idents: Vec<_> = split.data.iter().map(|(id, _)| id.clone()).collect();
+    let data_types: Vec<_> = split.data.iter().map(|(_, ty)| ty.clone()).collect();
+    let batch_args_struct = format_ident!("__{}BatchArgs", fn_name);
+
+    let flush_body = if args.trace {
+        quote! {
+            // `batch` means exactly one IPC crossing carries every row
+            // queued since the last flush, so `trace` attributes the whole
+            // round-trip to a single "{cmd}" sample rather than timing each
+            // queued call -- a queued call never touches the boundary, so
+            // there is nothing per-row to time.
+            let __t0 = js_sys::Date::now();
+            let __js_args = tauri_glue::rt::to_js_value(&#batch_args_struct { args: &__batch });
+            let __t1 = js_sys::Date::now();
+            let __js_result = tauri_glue::rt::invoke_raw(#batch_cmd, __js_args).await;
+            let __t2 = js_sys::Date::now();
+            let __result: Vec<Result<#ok, #err>> = match __js_result {
+                Ok(__ok) => tauri_glue::rt::from_js_value(__ok),
+                Err(__rejected) => {
+                    let __typed: #err = tauri_glue::rt::from_js_error(__rejected);
+                    __batch.iter().map(|_| Err(__typed.clone())).collect()
+                }
+            };
+            let __t3 = js_sys::Date::now();
+            tauri_glue::metrics::record(#cmd, __t1 - __t0, __t2 - __t1, __t3 - __t2);
+            __result
+        }
+    } else {
+        quote! {
+            tauri_glue::rt::invoke::<_, Vec<Result<#ok, #err>>, #err>(
+                #batch_cmd,
+                &#batch_args_struct { args: &__batch },
+            )
+            .await
+            .unwrap_or_else(|__e| __batch.iter().map(|_| Err(__e.clone())).collect())
+        }
+    };
+
+    quote! {
+        thread_local! {
+            static #queue_name: std::cell::RefCell<Vec<(#(#data_types,)*)>> =
+                std::cell::RefCell::new(Vec::new());
+        }
+
+        /// `batch` buffers this call on a thread-local queue instead of
+        /// firing an invoke per row; nothing crosses the IPC boundary until
+        /// [`#flush_name`] is awaited.
+        #vis fn #fn_name(#(#data_idents: #data_types),*) {
+            #queue_name.with(|__q| __q.borrow_mut().push((#(#data_idents,)*)));
+        }
+
+        #vis async fn #flush_name() -> Vec<Result<#ok, #err>> {
+            let __batch = #queue_name.with(|__q| __q.take());
+            if __batch.is_empty() {
+                return Vec::new();
+            }
+            // The struct name is derived from the command's snake_case
+            // identifier, so it can't itself be upper camel case.
+            #[allow(non_camel_case_types)]
+            #[derive(serde::Serialize)]
+            struct #batch_args_struct<'a> {
+                args: &'a Vec<(#(#data_types,)*)>,
+            }
+            #flush_body
+        }
+    }
+    .into()
+}
+
+/// Generates a per-command trait enumerating the types listed in
+/// `types(..)`, each mapping to the Tauri command name its `command_types`
+/// counterpart registered for that instantiation, plus a single generic
+/// `fn` that dispatches to the right command name via the trait constant.
+fn bind_command_types(args: MacroArgs, decl: ForeignItemFn) -> TokenStream {
+    let vis = &decl.vis;
+    let sig = &decl.sig;
+    let fn_name = &sig.ident;
+    let cmd = command_name(&args, fn_name);
+    let split = split_params(&sig.inputs);
+    let (ok, err) = result_ok_err(&sig.output)
+        .expect("#[tauri_glue::bind_command(types(..))] requires a `Result<T, E>` return type");
+    let (generic, concrete) = split
+        .data
+        .split_last()
+        .expect("#[tauri_glue::bind_command(types(..))] needs a generic data parameter");
+    let generic_ident = &generic.0;
+    let concrete_idents: Vec<_> = concrete.iter().map(|(id, _)| id.clone()).collect();
+    let concrete_types: Vec<_> = concrete.iter().map(|(_, ty)| ty.clone()).collect();
+
+    let trait_name = format_ident!(
+        "{}{}Types",
+        fn_name.to_string()[..1].to_uppercase(),
+        &fn_name.to_string()[1..]
+    );
+    let args_struct = format_ident!("__{}Args", fn_name);
+
+    let impls = args.types.iter().map(|ty| {
+        let suffix = type_suffix(ty);
+        let wrapper_cmd = format!("{cmd}_{suffix}");
+        quote! {
+            impl #trait_name for #ty {
+                const COMMAND: &'static str = #wrapper_cmd;
+            }
+        }
+    });
+
+    quote! {
+        /// Per-command trait enumerating the concrete monomorphizations
+        /// listed in `types(..)`; each maps to the Tauri command name the
+        /// backend registered for that instantiation, so two
+        /// instantiations are genuinely distinct invoke handlers rather
+        /// than one shared one.
+        #vis trait #trait_name: serde::Serialize + serde::de::DeserializeOwned {
+            const COMMAND: &'static str;
+        }
+
+        #(#impls)*
+
+        #vis async fn #fn_name<T: #trait_name>(
+            #(#concrete_idents: #concrete_types,)*
+            #generic_ident: T,
+        ) -> Result<#ok, #err> {
+            // The struct name is derived from the command's snake_case
+            // identifier, so it can't itself be upper camel case.
+            #[allow(non_camel_case_types)]
+            #[derive(serde::Serialize)]
+            struct #args_struct<T> {
+                #(#concrete_idents: #concrete_types,)*
+                #generic_ident: T,
+            }
+            tauri_glue::rt::invoke(
+                T::COMMAND,
+                &#args_struct { #(#concrete_idents,)* #generic_ident },
+            )
+            .await
+        }
+    }
+    .into()
+}
+
+/// `#[tauri_glue::emit(name = ..)]`
+///
+/// Backend side. Generates a typed `emit(payload: T)` helper around
+/// `Manager::emit_all`, the counterpart to `listen` on the frontend.
+#[proc_macro_attribute]
+pub fn emit(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
+    let decl = parse_macro_input!(item as ForeignItemFn);
+    let event_name = args
+        .name
+        .expect("#[tauri_glue::emit(name = ..)] requires a `name`")
+        .to_string();
+
+    let vis = &decl.vis;
+    let sig = &decl.sig;
+    let fn_name = &sig.ident;
+    let split = split_params(&sig.inputs);
+    let handle_param = split
+        .injected
+        .first()
+        .expect("#[tauri_glue::emit] needs a `&tauri::AppHandle`/`&tauri::Window` parameter");
+    let handle_ident = injected_idents(&split)
+        .into_iter()
+        .next()
+        .expect("#[tauri_glue::emit] needs a `&tauri::AppHandle`/`&tauri::Window` parameter");
+    let (payload_ident, payload_ty) = split
+        .data
+        .first()
+        .expect("#[tauri_glue::emit] needs a payload parameter");
+
+    quote! {
+        #vis fn #fn_name(#handle_param, #payload_ident: #payload_ty) {
+            let _ = tauri::Manager::emit_all(#handle_ident, #event_name, #payload_ident);
+        }
+    }
+    .into()
+}
+
+/// `#[tauri_glue::listen(name = ..)]`
+///
+/// Frontend side. Generates a function returning a `ReadSignal<Option<T>>`
+/// that subscribes to a Tauri event on first call -- later calls reuse the
+/// same signal instead of opening a second subscription -- and pushes every
+/// emitted payload through the paired write side.
+#[proc_macro_attribute]
+pub fn listen(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as MacroArgs);
+    let decl = parse_macro_input!(item as ForeignItemFn);
+    let event_name = args
+        .name
+        .expect("#[tauri_glue::listen(name = ..)] requires a `name`")
+        .to_string();
+
+    let vis = &decl.vis;
+    let sig = &decl.sig;
+    let fn_name = &sig.ident;
+    let cx_param = sig
+        .inputs
+        .first()
+        .expect("#[tauri_glue::listen] needs a `leptos::Scope` parameter");
+    let cx_ident = match cx_param {
+        FnArg::Typed(t) => match &*t.pat {
+            Pat::Ident(id) => id.ident.clone(),
+            _ => format_ident!("cx"),
+        },
+        _ => format_ident!("cx"),
+    };
+    let ReturnType::Type(_, ret_ty) = &sig.output else {
+        panic!("#[tauri_glue::listen] requires a `ReadSignal<Option<T>>` return type")
+    };
+    let signal_static = format_ident!("__{}_SIGNAL", fn_name.to_string().to_uppercase());
+
+    quote! {
+        thread_local! {
+            static #signal_static: std::cell::RefCell<Option<#ret_ty>> =
+                std::cell::RefCell::new(None);
+        }
+
+        #vis fn #fn_name(#cx_param) -> #ret_ty {
+            if let Some(existing) = #signal_static.with(|s| *s.borrow()) {
+                return existing;
+            }
+            let (read, write) = leptos::create_signal(#cx_ident, None);
+            #signal_static.with(|s| *s.borrow_mut() = Some(read));
+            leptos::spawn_local(async move {
+                tauri_glue::rt::listen(#event_name, move |payload| {
+                    write.set(Some(payload));
+                })
+                .await;
+            });
+            read
+        }
+    }
+    .into()
+}
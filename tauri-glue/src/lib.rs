@@ -0,0 +1,183 @@
+//! Runtime support for the `tauri_glue` attribute macros: the actual
+//! `invoke` plumbing the generated code calls into. The macros themselves
+//! live in `tauri-glue-macros`; this crate is the thing `use tauri_glue::*`
+//! pulls in on both the backend and the frontend.
+
+pub use tauri_glue_macros::{bind_command, command, emit, listen};
+
+/// wasm-side `invoke`/`listen` plumbing. Only meaningful in the frontend
+/// (wasm32) build; the backend never calls into it.
+#[cfg(target_arch = "wasm32")]
+pub mod rt {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], js_name = invoke)]
+        fn invoke_js(cmd: &str, args: JsValue) -> js_sys::Promise;
+
+        #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+        fn listen_js(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> js_sys::Promise;
+    }
+
+    /// Subscribes to a backend-emitted Tauri event, deserializing each
+    /// payload into `T` and handing it to `on_event`. Leaks the JS closure
+    /// for the lifetime of the page, matching the subscription never being
+    /// explicitly torn down by the generated `listen` wrapper.
+    pub async fn listen<T, F>(event: &str, mut on_event: F)
+    where
+        T: DeserializeOwned,
+        F: FnMut(T) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(JsValue)>::new(move |raw: JsValue| {
+            let payload = js_sys::Reflect::get(&raw, &JsValue::from_str("payload"))
+                .unwrap_or(JsValue::UNDEFINED);
+            if let Ok(value) = serde_wasm_bindgen::from_value::<T>(payload) {
+                on_event(value);
+            }
+        });
+        let _ = JsFuture::from(listen_js(event, &closure)).await;
+        closure.forget();
+    }
+
+    /// Crosses the IPC boundary once, returning the raw JS success/rejection
+    /// value without touching serde. Exists so `trace`d commands can time
+    /// serialization, the round-trip, and deserialization separately.
+    pub async fn invoke_raw(cmd: &str, args: JsValue) -> Result<JsValue, JsValue> {
+        JsFuture::from(invoke_js(cmd, args)).await
+    }
+
+    /// Serializes `value` into the `JsValue` the IPC boundary expects.
+    /// Exposed so `trace`d generated code -- which lives in whatever crate
+    /// declared the `bind_command`, not here -- can time serialization
+    /// without needing its own `serde-wasm-bindgen` dependency.
+    pub fn to_js_value<A: Serialize>(value: &A) -> JsValue {
+        serde_wasm_bindgen::to_value(value).expect("failed to serialize invoke args")
+    }
+
+    /// Deserializes a successful invoke response out of its `JsValue`.
+    pub fn from_js_value<T: DeserializeOwned>(value: JsValue) -> T {
+        serde_wasm_bindgen::from_value(value).expect("failed to deserialize invoke response")
+    }
+
+    /// Deserializes the typed error payload carried across an invoke
+    /// rejection.
+    pub fn from_js_error<E: DeserializeOwned>(value: JsValue) -> E {
+        serde_wasm_bindgen::from_value(value).expect(
+            "failed to deserialize the typed error payload carried across the invoke rejection",
+        )
+    }
+
+    /// Serializes `args`, invokes `cmd`, and deserializes the response (or
+    /// the typed error carried across the rejection channel) in one call.
+    pub async fn invoke<A, T, E>(cmd: &str, args: &A) -> Result<T, E>
+    where
+        A: Serialize,
+        T: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        match invoke_raw(cmd, to_js_value(args)).await {
+            Ok(ok) => Ok(from_js_value(ok)),
+            Err(err) => Err(from_js_error(err)),
+        }
+    }
+}
+
+/// Per-command IPC latency histograms, fed by `trace`d `bind_command`s.
+/// Isolated from `rt` because it has no wasm dependency of its own --
+/// `record`/`snapshot`/`reset` are plain data structures the generated code
+/// happens to call from wasm.
+pub mod metrics {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// Aggregated view of one command's samples. `mean`/`p99` are computed
+    /// over the total (serialize + invoke + deserialize) duration of each
+    /// call; `phase_means` breaks that total down by phase so users can
+    /// tell serialization cost apart from the round-trip itself.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Stats {
+        pub count: u64,
+        pub min: f64,
+        pub max: f64,
+        pub mean: f64,
+        pub p99: f64,
+        pub phase_means: (f64, f64, f64),
+    }
+
+    #[derive(Default)]
+    struct Histogram {
+        totals: Vec<f64>,
+        phase_sums: (f64, f64, f64),
+    }
+
+    impl Histogram {
+        fn push(&mut self, serialize: f64, invoke: f64, deserialize: f64) {
+            self.totals.push(serialize + invoke + deserialize);
+            self.phase_sums.0 += serialize;
+            self.phase_sums.1 += invoke;
+            self.phase_sums.2 += deserialize;
+        }
+
+        fn stats(&self) -> Stats {
+            let count = self.totals.len();
+            if count == 0 {
+                return Stats::default();
+            }
+            let mut sorted = self.totals.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let sum: f64 = sorted.iter().sum();
+            let p99_index = ((count as f64) * 0.99).ceil() as usize;
+            Stats {
+                count: count as u64,
+                min: sorted[0],
+                max: sorted[count - 1],
+                mean: sum / count as f64,
+                p99: sorted[p99_index.min(count) - 1],
+                phase_means: (
+                    self.phase_sums.0 / count as f64,
+                    self.phase_sums.1 / count as f64,
+                    self.phase_sums.2 / count as f64,
+                ),
+            }
+        }
+    }
+
+    thread_local! {
+        static HISTOGRAMS: RefCell<HashMap<&'static str, Histogram>> = RefCell::new(HashMap::new());
+    }
+
+    /// Records one traced call: the serialization, invoke round-trip, and
+    /// deserialization durations, all in milliseconds (the resolution of
+    /// `js_sys::Date::now`/`performance.now`).
+    pub fn record(command: &'static str, serialize_ms: f64, invoke_ms: f64, deserialize_ms: f64) {
+        HISTOGRAMS.with(|histograms| {
+            histograms
+                .borrow_mut()
+                .entry(command)
+                .or_default()
+                .push(serialize_ms, invoke_ms, deserialize_ms);
+        });
+    }
+
+    /// Snapshot of every traced command's stats as of now.
+    pub fn snapshot() -> HashMap<&'static str, Stats> {
+        HISTOGRAMS.with(|histograms| {
+            histograms
+                .borrow()
+                .iter()
+                .map(|(name, histogram)| (*name, histogram.stats()))
+                .collect()
+        })
+    }
+
+    /// Zeroes every command's histogram, e.g. when the UI's Clear button is
+    /// pressed.
+    pub fn reset() {
+        HISTOGRAMS.with(|histograms| histograms.borrow_mut().clear());
+    }
+}